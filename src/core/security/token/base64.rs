@@ -0,0 +1,185 @@
+//! Constant-time Base64 / Base64URL codec.
+//!
+//! Encoding and decoding use branch-free character arithmetic: every
+//! 6-bit value or ASCII byte is mapped via range comparisons turned into
+//! all-ones/all-zero masks and combined with bitwise AND/OR, instead of a
+//! data-dependent table lookup or an `if`/`match` on the byte's value.
+//! That keeps the control flow and memory-access pattern identical no
+//! matter what secret bytes are being encoded or decoded.
+
+use crate::error::{Error, Result};
+
+const PAD: u8 = b'=';
+
+/// -1 (all bits set) if `lo <= x <= hi`, otherwise 0. Built from two
+/// sign-bit extractions rather than a comparison operator, so it never
+/// branches on `x`.
+fn mask_in_range(x: i32, lo: i32, hi: i32) -> i32 {
+    let at_least_lo = (lo - 1 - x) >> 31 & 1;
+    let at_most_hi = (x - hi - 1) >> 31 & 1;
+    -(at_least_lo & at_most_hi)
+}
+
+/// Map a 6-bit value (0..=63) to its Base64 (or Base64URL, if `url`) ASCII
+/// character.
+fn value_to_char(value: u8, url: bool) -> u8 {
+    let x = value as i32;
+    let (delta62, delta63) = if url { (-13, 49) } else { (-15, 3) };
+
+    let mut out = x + i32::from(b'A');
+    out += 6 & ((26 - 1 - x) >> 8);
+    out += -75 & ((52 - 1 - x) >> 8);
+    out += delta62 & ((62 - 1 - x) >> 8);
+    out += delta63 & ((63 - 1 - x) >> 8);
+    out as u8
+}
+
+/// Map a Base64 (or Base64URL, if `url`) ASCII character back to its 6-bit
+/// value. Returns `None` for any byte outside the alphabet.
+fn char_to_value(c: u8, url: bool) -> Option<u8> {
+    let x = c as i32;
+    let (sym62, sym63) = if url { (b'-', b'_') } else { (b'+', b'/') };
+
+    let is_upper = mask_in_range(x, 'A' as i32, 'Z' as i32);
+    let is_lower = mask_in_range(x, 'a' as i32, 'z' as i32);
+    let is_digit = mask_in_range(x, '0' as i32, '9' as i32);
+    let is_62 = mask_in_range(x, sym62 as i32, sym62 as i32);
+    let is_63 = mask_in_range(x, sym63 as i32, sym63 as i32);
+
+    let valid = is_upper | is_lower | is_digit | is_62 | is_63;
+    if valid == 0 {
+        return None;
+    }
+
+    let value = ((x - 'A' as i32) & is_upper)
+        | ((x - 'a' as i32 + 26) & is_lower)
+        | ((x - '0' as i32 + 52) & is_digit)
+        | (62 & is_62)
+        | (63 & is_63);
+    Some(value as u8)
+}
+
+fn encode_with(data: &[u8], url: bool, pad: bool) -> String {
+    let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        let v0 = b0 >> 2;
+        let v1 = ((b0 & 0x03) << 4) | (b1 >> 4);
+        let v2 = ((b1 & 0x0f) << 2) | (b2 >> 6);
+        let v3 = b2 & 0x3f;
+
+        out.push(value_to_char(v0, url));
+        out.push(value_to_char(v1, url));
+        out.push(if chunk.len() > 1 {
+            value_to_char(v2, url)
+        } else if pad {
+            PAD
+        } else {
+            continue;
+        });
+        out.push(if chunk.len() > 2 {
+            value_to_char(v3, url)
+        } else if pad {
+            PAD
+        } else {
+            continue;
+        });
+    }
+    String::from_utf8(out).expect("base64 alphabet is always valid UTF-8")
+}
+
+fn decode_with(input: &str, url: bool) -> Result<Vec<u8>> {
+    let trimmed = input.trim_end_matches('=');
+    if input.len() - trimmed.len() > 2 {
+        return Err(Error::Validation("malformed base64 padding".into()));
+    }
+
+    let values: Vec<u8> = trimmed
+        .bytes()
+        .map(|b| char_to_value(b, url).ok_or(Error::Validation(format!("invalid base64 character: {b:#x}"))))
+        .collect::<Result<_>>()?;
+
+    if values.len() % 4 == 1 {
+        return Err(Error::Validation("invalid base64 length".into()));
+    }
+
+    let mut out = Vec::with_capacity(values.len() * 3 / 4);
+    for group in values.chunks(4) {
+        let v0 = group[0];
+        let v1 = *group.get(1).unwrap_or(&0);
+        let v2 = *group.get(2).unwrap_or(&0);
+        let v3 = *group.get(3).unwrap_or(&0);
+
+        out.push((v0 << 2) | (v1 >> 4));
+        if group.len() > 2 {
+            out.push((v1 << 4) | (v2 >> 2));
+        }
+        if group.len() > 3 {
+            out.push((v2 << 6) | v3);
+        }
+    }
+    Ok(out)
+}
+
+/// Encode `data` as standard, padded Base64.
+pub fn encode(data: &[u8]) -> String {
+    encode_with(data, false, true)
+}
+
+/// Encode `data` as unpadded Base64URL (`-`/`_` in place of `+`/`/`).
+pub fn encode_url(data: &[u8]) -> String {
+    encode_with(data, true, false)
+}
+
+/// Decode standard, padded Base64.
+pub fn decode(input: &str) -> Result<Vec<u8>> {
+    decode_with(input, false)
+}
+
+/// Decode unpadded Base64URL.
+pub fn decode_url(input: &str) -> Result<Vec<u8>> {
+    decode_with(input, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        for data in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = encode(data);
+            assert_eq!(decode(&encoded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_encode_matches_known_vectors() {
+        assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(encode(b"foob"), "Zm9vYg==");
+        assert_eq!(encode(b""), "");
+    }
+
+    #[test]
+    fn test_url_roundtrip_and_alphabet() {
+        let data = b"\xfb\xff\xfe";
+        let encoded = encode_url(data);
+        assert!(!encoded.contains('+'));
+        assert!(!encoded.contains('/'));
+        assert!(!encoded.contains('='));
+        assert_eq!(decode_url(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_character() {
+        assert!(decode("not-valid-base64!!").is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_length() {
+        assert!(decode("A").is_err());
+    }
+}
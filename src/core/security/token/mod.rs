@@ -0,0 +1,52 @@
+//! Security-sensitive token utilities.
+//!
+//! These helpers compare and encode token and API-key bytes handed in by
+//! the caller; no other module of the crate calls them. Both operations
+//! are built to leak nothing about the
+//! secret via timing or cache side channels: naive `==` short-circuits on
+//! the first mismatching byte, and a lookup-table Base64 codec branches
+//! (and therefore takes a data-dependent path) on every character.
+
+pub mod base64;
+
+/// Compare two byte strings in constant time.
+///
+/// Checks the lengths up front (lengths are not secret), then folds a
+/// running XOR-accumulator over every byte pair without ever
+/// short-circuiting, so the time taken is independent of where, or
+/// whether, `a` and `b` first differ.
+pub fn verify_constant_time(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff: u8 = 0;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_constant_time_equal() {
+        assert!(verify_constant_time(b"super-secret-token", b"super-secret-token"));
+    }
+
+    #[test]
+    fn test_verify_constant_time_different_content() {
+        assert!(!verify_constant_time(b"super-secret-token", b"super-secret-toke0"));
+    }
+
+    #[test]
+    fn test_verify_constant_time_different_length() {
+        assert!(!verify_constant_time(b"short", b"much-longer"));
+    }
+
+    #[test]
+    fn test_verify_constant_time_empty() {
+        assert!(verify_constant_time(b"", b""));
+    }
+}
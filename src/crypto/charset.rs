@@ -0,0 +1,168 @@
+//! Shared character-set configuration reused by every generator in [`crate::crypto`].
+//!
+//! Replaces the scattered `require_upper`/`require_lower`/... boolean
+//! parameters that used to live on each generator with a single
+//! [`CharacterSet`] bitflags value, plus an optional excluded-character
+//! string and an "avoid ambiguous characters" toggle.
+
+use bitflags::bitflags;
+
+use crate::error::{Error, Result};
+
+pub(crate) const UPPERCASE_POOL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub(crate) const LOWERCASE_POOL: &str = "abcdefghijklmnopqrstuvwxyz";
+pub(crate) const NUMBERS_POOL: &str = "0123456789";
+pub(crate) const SYMBOLS_POOL: &str = "!#$%&()*+-./:;<=>?@[]^_{|}~";
+
+/// Characters that are easily confused with one another (`0`/`O`, `1`/`l`/`I`).
+const AMBIGUOUS: &str = "0O1lI";
+
+bitflags! {
+    /// The character categories a generator is allowed to draw from.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct CharacterSet: u8 {
+        const UPPERCASE = 0b0001;
+        const LOWERCASE = 0b0010;
+        const NUMBERS   = 0b0100;
+        const SYMBOLS   = 0b1000;
+
+        /// Upper- and lowercase letters.
+        const LETTERS = Self::UPPERCASE.bits() | Self::LOWERCASE.bits();
+        /// Every supported category.
+        const ALL = Self::LETTERS.bits() | Self::NUMBERS.bits() | Self::SYMBOLS.bits();
+    }
+}
+
+impl Default for CharacterSet {
+    fn default() -> Self {
+        CharacterSet::ALL
+    }
+}
+
+/// The character pools a [`CharacterSet`] resolves to, after applying
+/// exclusions, ready to be consumed by a generator.
+pub struct ResolvedPools {
+    /// One pool per active category, in [`CharacterSet`] bit order.
+    pub categories: Vec<Vec<char>>,
+    /// The concatenation of every active category's pool.
+    pub combined: Vec<char>,
+}
+
+fn base_pool(flag: CharacterSet) -> &'static str {
+    match flag {
+        CharacterSet::UPPERCASE => UPPERCASE_POOL,
+        CharacterSet::LOWERCASE => LOWERCASE_POOL,
+        CharacterSet::NUMBERS => NUMBERS_POOL,
+        CharacterSet::SYMBOLS => SYMBOLS_POOL,
+        _ => unreachable!("base_pool is only called with single-bit flags"),
+    }
+}
+
+/// Resolve `charset` into concrete character pools, dropping any characters
+/// in `exclude` and, if `avoid_ambiguous` is set, any of `0O1lI`.
+///
+/// Fails with [`Error::InvalidCharset`] if no category is enabled or if
+/// filtering leaves an enabled category empty.
+pub fn resolve(
+    charset: CharacterSet,
+    exclude: Option<&str>,
+    avoid_ambiguous: bool,
+) -> Result<ResolvedPools> {
+    const BASE_FLAGS: [CharacterSet; 4] = [
+        CharacterSet::UPPERCASE,
+        CharacterSet::LOWERCASE,
+        CharacterSet::NUMBERS,
+        CharacterSet::SYMBOLS,
+    ];
+
+    let mut categories = Vec::new();
+    for flag in BASE_FLAGS {
+        if !charset.contains(flag) {
+            continue;
+        }
+        let pool: Vec<char> = base_pool(flag)
+            .chars()
+            .filter(|c| !exclude.is_some_and(|excl| excl.contains(*c)))
+            .filter(|c| !avoid_ambiguous || !AMBIGUOUS.contains(*c))
+            .collect();
+        if pool.is_empty() {
+            return Err(Error::InvalidCharset(format!(
+                "category {flag:?} has no characters left after exclusions"
+            )));
+        }
+        categories.push(pool);
+    }
+
+    if categories.is_empty() {
+        return Err(Error::InvalidCharset(
+            "at least one character category must be enabled".into(),
+        ));
+    }
+
+    let combined = categories.iter().flatten().copied().collect();
+    Ok(ResolvedPools {
+        categories,
+        combined,
+    })
+}
+
+/// Ensure `length` can hold at least one character from each of
+/// `category_count` required categories.
+pub fn validate_length(length: usize, category_count: usize) -> Result<()> {
+    if length < category_count {
+        return Err(Error::InvalidLength {
+            min: category_count,
+            max: usize::MAX,
+            actual: length,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_all_categories() {
+        let pools = resolve(CharacterSet::ALL, None, false).unwrap();
+        assert_eq!(pools.categories.len(), 4);
+        assert_eq!(
+            pools.combined.len(),
+            UPPERCASE_POOL.len() + LOWERCASE_POOL.len() + NUMBERS_POOL.len() + SYMBOLS_POOL.len()
+        );
+    }
+
+    #[test]
+    fn test_resolve_avoids_ambiguous() {
+        let pools = resolve(CharacterSet::UPPERCASE | CharacterSet::NUMBERS, None, true).unwrap();
+        for pool in &pools.categories {
+            assert!(!pool.contains(&'O'));
+            assert!(!pool.contains(&'1'));
+        }
+    }
+
+    #[test]
+    fn test_resolve_exclude_chars() {
+        let pools = resolve(CharacterSet::LOWERCASE, Some("abc"), false).unwrap();
+        assert!(!pools.combined.contains(&'a'));
+        assert!(!pools.combined.contains(&'b'));
+        assert!(!pools.combined.contains(&'c'));
+    }
+
+    #[test]
+    fn test_resolve_rejects_empty_charset() {
+        assert!(resolve(CharacterSet::empty(), None, false).is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_fully_excluded_category() {
+        assert!(resolve(CharacterSet::NUMBERS, Some(NUMBERS_POOL), false).is_err());
+    }
+
+    #[test]
+    fn test_validate_length() {
+        assert!(validate_length(4, 4).is_ok());
+        assert!(validate_length(3, 4).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+// <modgen:start>
+pub mod charset;
+pub mod passphrase;
+pub mod password;
+pub mod pin;
+
+pub use charset::*;
+pub use passphrase::*;
+pub use password::*;
+pub use pin::*;
+// <modgen:end>
@@ -0,0 +1,206 @@
+//! Diceware/EFF-style passphrase generation.
+//!
+//! Words are drawn uniformly at random (via rejection sampling, so the
+//! crate's CSPRNG introduces no modulo bias) from a bundled wordlist, or
+//! from a caller-supplied one via `PassphraseOptions::wordlist`.
+//!
+//! The bundled list is a placeholder: real, common English words (so
+//! passphrases stay memorable and pronounceable), but not the literal EFF
+//! long wordlist, which this crate doesn't vendor. Callers who need the
+//! canonical diceware wordlist (or a localized equivalent) should pass it
+//! in via `wordlist`.
+//!
+//! UNRESOLVED (chunk0-3): the original request asked for the real EFF long
+//! wordlist specifically, not an equivalent. This environment has no route
+//! to eff.org to vendor it, and a second placeholder would just repeat the
+//! thing that was already flagged once. Swapping in "close enough" words
+//! again isn't this crate's call to make unilaterally — whether to accept
+//! the placeholder as-is, have the caller supply the real list via
+//! `wordlist`, or block on someone vendoring the licensed file is a
+//! decision for whoever filed chunk0-3. Do not resolve this by substituting
+//! another wordlist without that sign-off.
+
+use rand::Rng;
+
+use crate::error::{Error, Result};
+
+/// Bundled default wordlist, one word per line. See the module docs for
+/// why this is a placeholder rather than the literal EFF long wordlist.
+const BUNDLED_WORDLIST: &str = include_str!("wordlists/placeholder_wordlist.txt");
+
+/// A wordlist shorter than this cannot provide a reasonable security margin.
+const MIN_WORDLIST_LEN: usize = 1_000;
+
+/// Configuration for a single passphrase.
+#[derive(Debug, Clone)]
+pub struct PassphraseOptions<'a> {
+    pub word_count: usize,
+    pub separator: String,
+    pub capitalize: bool,
+    pub include_number: bool,
+    /// Wordlist to draw from; defaults to the bundled list when `None`.
+    pub wordlist: Option<&'a [&'a str]>,
+}
+
+impl Default for PassphraseOptions<'_> {
+    fn default() -> Self {
+        Self {
+            word_count: 6,
+            separator: "-".to_string(),
+            capitalize: false,
+            include_number: false,
+            wordlist: None,
+        }
+    }
+}
+
+/// A generated passphrase, together with its estimated entropy.
+#[derive(Debug, Clone)]
+pub struct GeneratedPassphrase {
+    pub passphrase: String,
+    pub entropy_bits: f64,
+}
+
+fn bundled_wordlist() -> Vec<&'static str> {
+    BUNDLED_WORDLIST.lines().filter(|w| !w.is_empty()).collect()
+}
+
+/// Pick a uniformly random index in `0..len` using rejection sampling, so
+/// the result carries zero modulo bias regardless of `len`.
+fn sample_index(rng: &mut impl Rng, len: usize) -> usize {
+    let len = len as u32;
+    let zone = (u32::MAX / len) * len;
+    loop {
+        let candidate = rng.gen::<u32>();
+        if candidate < zone {
+            return (candidate % len) as usize;
+        }
+    }
+}
+
+fn capitalize_word(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Generate a passphrase per `options`, returning it alongside its
+/// estimated entropy in bits (`word_count * log2(list_len)`, plus the
+/// entropy contributed by an injected digit).
+pub fn generate_passphrase(options: &PassphraseOptions) -> Result<GeneratedPassphrase> {
+    if options.word_count == 0 {
+        return Err(Error::Validation("word_count must be at least 1".into()));
+    }
+
+    let owned_wordlist;
+    let wordlist: &[&str] = match options.wordlist {
+        Some(list) => list,
+        None => {
+            owned_wordlist = bundled_wordlist();
+            &owned_wordlist
+        }
+    };
+
+    if wordlist.len() < MIN_WORDLIST_LEN {
+        return Err(Error::Entropy(format!(
+            "wordlist has only {} words, need at least {MIN_WORDLIST_LEN}",
+            wordlist.len()
+        )));
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut words: Vec<String> = (0..options.word_count)
+        .map(|_| {
+            let word = wordlist[sample_index(&mut rng, wordlist.len())];
+            if options.capitalize {
+                capitalize_word(word)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect();
+
+    let mut entropy_bits = options.word_count as f64 * (wordlist.len() as f64).log2();
+
+    if options.include_number {
+        let word_idx = sample_index(&mut rng, words.len());
+        let digit = sample_index(&mut rng, 10);
+        let word = &mut words[word_idx];
+        let char_idx = sample_index(&mut rng, word.chars().count() + 1);
+        let mut chars: Vec<char> = word.chars().collect();
+        chars.insert(char_idx, char::from_digit(digit as u32, 10).unwrap());
+        *word = chars.into_iter().collect();
+        entropy_bits += 10f64.log2();
+    }
+
+    Ok(GeneratedPassphrase {
+        passphrase: words.join(&options.separator),
+        entropy_bits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_passphrase_word_count_and_separator() {
+        let options = PassphraseOptions {
+            word_count: 5,
+            separator: "_".to_string(),
+            ..PassphraseOptions::default()
+        };
+        let result = generate_passphrase(&options).unwrap();
+        assert_eq!(result.passphrase.split('_').count(), 5);
+    }
+
+    #[test]
+    fn test_generate_passphrase_capitalizes_each_word() {
+        let options = PassphraseOptions {
+            word_count: 4,
+            capitalize: true,
+            ..PassphraseOptions::default()
+        };
+        let result = generate_passphrase(&options).unwrap();
+        for word in result.passphrase.split(&options.separator) {
+            assert!(word.chars().next().unwrap().is_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_generate_passphrase_includes_a_digit() {
+        let options = PassphraseOptions {
+            include_number: true,
+            ..PassphraseOptions::default()
+        };
+        let result = generate_passphrase(&options).unwrap();
+        assert!(result.passphrase.chars().any(|c| c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn test_generate_passphrase_rejects_short_wordlist() {
+        let short_list = ["a", "b", "c"];
+        let options = PassphraseOptions {
+            wordlist: Some(&short_list),
+            ..PassphraseOptions::default()
+        };
+        assert!(generate_passphrase(&options).is_err());
+    }
+
+    #[test]
+    fn test_generate_passphrase_entropy_bits_scales_with_word_count() {
+        let short = generate_passphrase(&PassphraseOptions {
+            word_count: 3,
+            ..PassphraseOptions::default()
+        })
+        .unwrap();
+        let long = generate_passphrase(&PassphraseOptions {
+            word_count: 6,
+            ..PassphraseOptions::default()
+        })
+        .unwrap();
+        assert!(long.entropy_bits > short.entropy_bits);
+    }
+}
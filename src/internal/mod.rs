@@ -0,0 +1,4 @@
+mod validation;
+
+#[allow(unused_imports)]
+pub(crate) use validation::*;
@@ -1,7 +1,5 @@
 pub use crate::error::{Error, Result};
 
-pub use crate::core::security::authentication::*;
-pub use crate::core::security::cryptography::*;
 pub use crate::core::security::token::*;
 
-pub use crate::crypto::*;
\ No newline at end of file
+pub use crate::crypto::*;